@@ -0,0 +1,40 @@
+//! Builds the dictionary trie at compile time and drops it into `OUT_DIR`
+//! so `main.rs` can `include_bytes!` it instead of reading `dictionary.txt`
+//! off disk at runtime.
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use fxhash::FxBuildHasher;
+use serde::{Deserialize, Serialize};
+
+type FxHashMap<K, V> = HashMap<K, V, FxBuildHasher>;
+
+include!("src/trie_node.rs");
+
+fn insert(root: &mut TrieNode<()>, word: &str) {
+    let mut curr_node = root;
+    for c in word.chars() {
+        curr_node = curr_node.children.entry(c).or_default();
+    }
+    curr_node.value = Some(());
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=dictionary.txt");
+
+    let dictionary =
+        fs::read_to_string("dictionary.txt").expect("Failed to read dictionary.txt at build time");
+
+    let mut root: TrieNode<()> = TrieNode::default();
+    for word in dictionary.lines() {
+        insert(&mut root, word);
+    }
+
+    let bytes = bincode::serialize(&root).expect("Failed to serialize embedded trie");
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set");
+    let dest = Path::new(&out_dir).join("dictionary.trie.bin");
+    fs::write(dest, bytes).expect("Failed to write embedded trie to OUT_DIR");
+}
@@ -9,63 +9,120 @@ use crossterm::{
     terminal, ExecutableCommand, QueueableCommand,
 };
 use fxhash::FxBuildHasher;
+use serde::{Deserialize, Serialize};
 use std::{
-    char,
-    collections::{HashMap, HashSet},
+    collections::HashMap,
     fs,
-    io::{stdout, Cursor, Write},
+    io::{stdout, Write},
     str::FromStr,
     thread,
     time::Duration,
-    usize,
 };
 
 type FxHashMap<K, V> = HashMap<K, V, FxBuildHasher>;
 
-#[derive(Default)]
-struct TrieNode {
-    is_end_of_word: bool,
-    children: FxHashMap<char, TrieNode>,
-}
+include!("trie_node.rs");
 
-struct Trie {
-    root: TrieNode,
+struct Trie<V> {
+    root: TrieNode<V>,
 }
 
-impl Trie {
+// The generic insert/get/contains/for_each API is exercised by the
+// `runtime-dictionary` feature build (see `build_trie_from_dictionary`
+// below) and by the tests at the bottom of this file; the default
+// embedded-trie build only ever constructs a `Trie` directly from a
+// deserialized `TrieNode`, so these are otherwise unused.
+impl<V> Trie<V> {
+    #[allow(dead_code)]
     fn new() -> Self {
         Trie {
             root: TrieNode::default(),
         }
     }
 
-    fn insert(&mut self, word: &str) {
+    #[allow(dead_code)]
+    fn insert(&mut self, word: &str, value: V) {
         let mut curr_node = &mut self.root;
 
         for c in word.chars() {
             curr_node = curr_node.children.entry(c).or_default();
         }
-        curr_node.is_end_of_word = true;
+        curr_node.value = Some(value);
     }
 
-    fn contains(&self, word: &str) -> bool {
+    #[allow(dead_code)]
+    fn get(&self, word: &str) -> Option<&V> {
         let mut curr_node = &self.root;
 
         for c in word.chars() {
             match curr_node.children.get(&c) {
                 Some(node) => curr_node = node,
-                None => return false,
+                None => return None,
             }
         }
 
-        curr_node.is_end_of_word
+        curr_node.value.as_ref()
+    }
+
+    #[allow(dead_code)]
+    fn contains(&self, word: &str) -> bool {
+        self.get(word).is_some()
+    }
+
+    /// Walks the whole trie depth-first, invoking `f` with the spelled
+    /// prefix and its value at every node that has one.
+    #[allow(dead_code)]
+    fn for_each<F: FnMut(&str, &V)>(&self, mut f: F) {
+        let mut prefix = String::new();
+        Self::for_each_node(&self.root, &mut prefix, &mut f);
+    }
+
+    fn for_each_node<F: FnMut(&str, &V)>(node: &TrieNode<V>, prefix: &mut String, f: &mut F) {
+        if let Some(value) = &node.value {
+            f(prefix, value);
+        }
+
+        for (&c, child) in &node.children {
+            prefix.push(c);
+            Self::for_each_node(child, prefix, f);
+            prefix.pop();
+        }
+    }
+}
+
+impl<V: Serialize> Trie<V> {
+    /// Serializes the trie to `path` with bincode so the next launch can
+    /// skip re-parsing the dictionary.
+    #[allow(dead_code)]
+    fn save(&self, path: &str) -> std::io::Result<()> {
+        let bytes =
+            bincode::serialize(&self.root).expect("Failed to serialize trie to bincode");
+        fs::write(path, bytes)
+    }
+}
+
+impl<V: for<'de> Deserialize<'de>> Trie<V> {
+    /// Loads a trie previously written by `save`. Returns an `io::Error`
+    /// (rather than panicking) on a truncated or corrupted cache file, so
+    /// `load_trie`'s `if let Ok(trie) = Trie::load(...)` can fall through
+    /// to rebuilding from the dictionary as documented.
+    #[allow(dead_code)]
+    fn load(path: &str) -> std::io::Result<Self> {
+        let bytes = fs::read(path)?;
+        let root: TrieNode<V> = bincode::deserialize(&bytes)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok(Trie { root })
     }
 }
 
 struct Grid {
     letters: String,
     grid_array: [[(char, bool); 4]; 4],
+    // Predate this backlog and aren't read anywhere yet; left in place
+    // rather than removed since nothing in these requests touches them.
+    #[allow(dead_code)]
     index: (u8, u8),
+    #[allow(dead_code)]
     curr_word: String,
 }
 
@@ -79,6 +136,9 @@ impl Grid {
         }
     }
 
+    /// Adds a tile to the next open cell. `c` may be `'?'` to mark a tile
+    /// as unknown/misread; `dfs` treats it as a wildcard that matches any
+    /// letter when solving.
     fn add(&mut self, c: char) {
         let len = self.letters.len();
         let row = len / 4;
@@ -108,10 +168,12 @@ impl Grid {
     }
 }
 
+#[cfg(feature = "runtime-dictionary")]
 struct Words {
     dictionary_words: Vec<String>,
 }
 
+#[cfg(feature = "runtime-dictionary")]
 impl Words {
     fn new(file_path: &str) -> Self {
         let content = fs::read_to_string(file_path).expect("Failed to read file.");
@@ -123,17 +185,93 @@ impl Words {
     }
 }
 
-fn find_words(grid: &Grid, trie: &Trie) -> Vec<String> {
-    let mut results = std::collections::HashSet::new();
+#[cfg(feature = "runtime-dictionary")]
+fn build_trie_from_dictionary(dictionary_path: &str) -> Trie<()> {
+    let words = Words::new(dictionary_path);
+    let mut trie = Trie::new();
+
+    for word in words.dictionary_words {
+        trie.insert(word.as_str(), ());
+    }
+
+    trie
+}
+
+/// Loads the trie, preferring a `cache_path` that is newer than
+/// `dictionary_path` over re-parsing the word list. Falls back to a fresh
+/// build (and refreshes the cache) whenever the cache is missing, stale,
+/// or unreadable.
+///
+/// Only compiled in with the `runtime-dictionary` feature; the default build
+/// embeds a trie compiled by `build.rs` instead, so there's nothing to load
+/// from the filesystem at all.
+#[cfg(feature = "runtime-dictionary")]
+fn load_trie(dictionary_path: &str, cache_path: &str) -> Trie<()> {
+    let cache_is_fresh = fs::metadata(cache_path)
+        .and_then(|cache_meta| cache_meta.modified())
+        .and_then(|cache_modified| {
+            fs::metadata(dictionary_path).and_then(|dict_meta| {
+                dict_meta
+                    .modified()
+                    .map(|dict_modified| cache_modified >= dict_modified)
+            })
+        })
+        .unwrap_or(false);
+
+    if cache_is_fresh {
+        if let Ok(trie) = Trie::load(cache_path) {
+            return trie;
+        }
+    }
+
+    let trie = build_trie_from_dictionary(dictionary_path);
+    let _ = trie.save(cache_path);
+    trie
+}
+
+/// The default build embeds a trie built from `dictionary.txt` by
+/// `build.rs`, so the solver starts up with zero filesystem dependency.
+/// Enable the `runtime-dictionary` feature to swap in a different
+/// dictionary without recompiling.
+#[cfg(not(feature = "runtime-dictionary"))]
+static EMBEDDED_TRIE_BYTES: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/dictionary.trie.bin"));
+
+#[cfg(not(feature = "runtime-dictionary"))]
+fn load_trie(_dictionary_path: &str, _cache_path: &str) -> Trie<()> {
+    let root: TrieNode<()> = bincode::deserialize(EMBEDDED_TRIE_BYTES)
+        .expect("Failed to deserialize trie embedded by build.rs");
+    Trie { root }
+}
+
+/// Word Hunt's point scale: 3 letters is worth 100, and every letter past
+/// that is worth disproportionately more, topping out at +400/letter
+/// beyond 7.
+fn score(len: usize) -> u32 {
+    match len {
+        0..=2 => 0,
+        3 => 100,
+        4 => 400,
+        5 => 800,
+        6 => 1400,
+        7 => 1800,
+        n => 1800 + 400 * (n as u32 - 7),
+    }
+}
+
+fn find_words(grid: &Grid, trie: &Trie<()>) -> Vec<(String, Vec<(usize, usize)>)> {
+    let mut results: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
     let mut visited = [[false; 4]; 4];
 
     for i in 0..4 {
         for j in 0..4 {
             let mut path = String::new();
+            let mut cell_path = Vec::new();
             dfs(
                 i,
                 j,
                 &mut path,
+                &mut cell_path,
                 &mut visited,
                 grid,
                 &trie.root,
@@ -142,23 +280,28 @@ fn find_words(grid: &Grid, trie: &Trie) -> Vec<String> {
         }
     }
 
-    let mut sorted: Vec<String> = results.into_iter().collect();
+    let mut sorted: Vec<(String, Vec<(usize, usize)>)> = results.into_iter().collect();
     sorted.sort_unstable_by(|a, b| {
-        let length_cmp = b.len().cmp(&a.len());
-        length_cmp.then_with(|| a.cmp(b))
+        let score_cmp = score(b.0.len()).cmp(&score(a.0.len()));
+        score_cmp.then_with(|| a.0.cmp(&b.0))
     });
 
     sorted
 }
 
+// `cell_path` pushed this past clippy's default 7-argument threshold;
+// threading the DFS state through a context struct isn't worth it for a
+// single recursive helper with one call site.
+#[allow(clippy::too_many_arguments)]
 fn dfs(
     row: usize,
     col: usize,
     path: &mut String,
+    cell_path: &mut Vec<(usize, usize)>,
     visited: &mut [[bool; 4]; 4],
     grid: &Grid,
-    trie: &TrieNode,
-    found_words: &mut HashSet<String>,
+    trie: &TrieNode<()>,
+    found_words: &mut HashMap<String, Vec<(usize, usize)>>,
 ) {
     if row >= 4 || col >= 4 || visited[row][col] {
         return;
@@ -168,59 +311,108 @@ fn dfs(
         return;
     };
 
-    let Some(next_node) = trie.children.get(&c) else {
-        return;
+    // A '?' tile is an unknown/misread letter: try every child instead of
+    // a single lookup, and record whichever concrete letter the prefix
+    // resolves to so the reported word is a real dictionary word.
+    let candidates: Vec<(char, &TrieNode<()>)> = if c == '?' {
+        trie.children.iter().map(|(&ch, node)| (ch, node)).collect()
+    } else {
+        match trie.children.get(&c) {
+            Some(next_node) => vec![(c, next_node)],
+            None => return,
+        }
     };
 
     visited[row][col] = true;
-    path.push(c);
 
-    if next_node.is_end_of_word && path.len() >= 3 {
-        found_words.insert(path.clone());
-    }
+    for (resolved_c, next_node) in candidates {
+        path.push(resolved_c);
+        cell_path.push((row, col));
 
-    for dr in -1..=1 {
-        for dc in -1..=1 {
-            if dr == 0 && dc == 0 {
-                continue;
-            }
+        if next_node.value.is_some() && path.len() >= 3 {
+            found_words
+                .entry(path.clone())
+                .or_insert_with(|| cell_path.clone());
+        }
 
-            let nr = row as i32 + dr;
-            let nc = col as i32 + dc;
-
-            if nr >= 0 && nr < 4 && nc >= 0 && nc < 4 {
-                dfs(
-                    nr as usize,
-                    nc as usize,
-                    path,
-                    visited,
-                    grid,
-                    next_node,
-                    found_words,
-                );
+        for dr in -1..=1 {
+            for dc in -1..=1 {
+                if dr == 0 && dc == 0 {
+                    continue;
+                }
+
+                let nr = row as i32 + dr;
+                let nc = col as i32 + dc;
+
+                if (0..4).contains(&nr) && (0..4).contains(&nc) {
+                    dfs(
+                        nr as usize,
+                        nc as usize,
+                        path,
+                        cell_path,
+                        visited,
+                        grid,
+                        next_node,
+                        found_words,
+                    );
+                }
             }
         }
+
+        path.pop();
+        cell_path.pop();
     }
 
     visited[row][col] = false;
-    path.pop();
+}
+
+/// Redraws the 4x4 grid in the upper-left region with `path` numbered
+/// 1..n and connected by arrows, so the player can see the exact swipe
+/// gesture that spells the selected word.
+fn render_word_path(stdout: &mut std::io::Stdout, grid: &Grid, path: &[(usize, usize)]) {
+    let mut order = [[None; 4]; 4];
+    for (step, &(r, c)) in path.iter().enumerate() {
+        order[r][c] = Some(step + 1);
+    }
+
+    for (r, row) in order.iter().enumerate() {
+        stdout.queue(cursor::MoveTo(0, r as u16)).unwrap();
+        let mut line = String::new();
+        for (c, cell) in row.iter().enumerate() {
+            let ch = grid.grid_array[r][c].0;
+            match cell {
+                Some(n) => line.push_str(&format!("{}{:<2} ", ch, n)),
+                None => line.push_str(&format!("{}   ", ch)),
+            }
+        }
+        stdout.write_all(line.as_bytes()).unwrap();
+        stdout
+            .execute(terminal::Clear(terminal::ClearType::UntilNewLine))
+            .unwrap();
+    }
+
+    stdout.queue(cursor::MoveTo(0, 4)).unwrap();
+    let trail: Vec<String> = path
+        .iter()
+        .enumerate()
+        .map(|(i, (r, c))| format!("{}:({},{})", i + 1, r, c))
+        .collect();
+    stdout.write_all(trail.join(" -> ").as_bytes()).unwrap();
+    stdout
+        .execute(terminal::Clear(terminal::ClearType::UntilNewLine))
+        .unwrap();
 }
 
 fn main() -> std::io::Result<()> {
     // initial state
-    let _ = terminal::enable_raw_mode().unwrap();
+    terminal::enable_raw_mode().unwrap();
     let mut stdout = stdout();
     let mut quit = false;
-    let mut found_words: Vec<String> = Vec::new();
+    let mut found_words: Vec<(String, Vec<(usize, usize)>)> = Vec::new();
+    let mut selected: Option<usize> = None;
 
     let mut grid = Grid::new();
-    let words = Words::new("dictionary.txt");
-    let mut trie = Trie::new();
-
-    for word in words.dictionary_words {
-        let str_word = word.as_str();
-        trie.insert(str_word);
-    }
+    let trie = load_trie("dictionary.txt", "dictionary.trie");
 
     let (mut term_w, mut term_h) = terminal::size().unwrap();
     let divider_char = "â”€";
@@ -232,20 +424,25 @@ fn main() -> std::io::Result<()> {
     while !quit {
         match read()? {
             Event::Key(event) => match event.code {
-                KeyCode::Char(c) => {
-                    if grid.letters.len() < grid.letters.capacity() {
-                        grid.add(c);
-                    }
+                KeyCode::Char(c) if grid.letters.len() < grid.letters.capacity() => {
+                    grid.add(c);
                 }
                 KeyCode::Enter => {
                     found_words = find_words(&grid, &trie);
-                    stdout.queue(cursor::MoveTo(0, 0)).unwrap();
-                    for (idx, word) in found_words.iter().enumerate().take((term_h - 3) as usize) {
-                        stdout.queue(cursor::MoveTo(0, idx as u16)).unwrap();
-                        stdout.write(word.as_bytes()).unwrap();
-                        stdout
-                            .execute(terminal::Clear(terminal::ClearType::UntilNewLine))
-                            .unwrap();
+                    selected = if found_words.is_empty() {
+                        None
+                    } else {
+                        Some(0)
+                    };
+                }
+                KeyCode::Up => {
+                    if let Some(sel) = selected {
+                        selected = Some(sel.saturating_sub(1));
+                    }
+                }
+                KeyCode::Down => {
+                    if let Some(sel) = selected {
+                        selected = Some((sel + 1).min(found_words.len() - 1));
                     }
                 }
                 KeyCode::Backspace => grid.delete(),
@@ -259,23 +456,151 @@ fn main() -> std::io::Result<()> {
             }
             _ => todo!(),
         }
+        // draw the swipe path for the selected word, then the results list
+        // beside it so the grid stays visible while browsing results
+        if let Some(sel) = selected {
+            if let Some((_, path)) = found_words.get(sel) {
+                render_word_path(&mut stdout, &grid, path);
+            }
+        }
+
+        // `render_word_path` fills columns 0..16 (four 4-char cells per row:
+        // letter + 2-wide number + space), so the results list needs to start
+        // past that or it stomps the numbered grid it's meant to sit beside.
+        let list_col = 18;
+        let max_score: u32 = found_words.iter().map(|(word, _)| score(word.len())).sum();
+        // Shares list_col with the results list below it, which in turn has
+        // to clear render_word_path's grid -- see the comment on list_col.
+        stdout.queue(cursor::MoveTo(list_col, 0)).unwrap();
+        stdout
+            .write_all(format!("Max reachable score: {}", max_score).as_bytes())
+            .unwrap();
+        stdout
+            .execute(terminal::Clear(terminal::ClearType::UntilNewLine))
+            .unwrap();
+
+        for (idx, (word, _)) in found_words.iter().enumerate().take((term_h - 4) as usize) {
+            stdout
+                .queue(cursor::MoveTo(list_col, (idx + 1) as u16))
+                .unwrap();
+            let marker = if Some(idx) == selected { "> " } else { "  " };
+            stdout
+                .write_all(format!("{}{} {}", marker, word, score(word.len())).as_bytes())
+                .unwrap();
+            stdout
+                .execute(terminal::Clear(terminal::ClearType::UntilNewLine))
+                .unwrap();
+        }
+
         // clear input line to account for deletes
         stdout.queue(cursor::MoveTo(0, term_h - 1)).unwrap();
         stdout.execute(terminal::Clear(terminal::ClearType::CurrentLine))?;
 
         // draw input divider
         stdout.queue(cursor::MoveTo(0, term_h - 2)).unwrap();
-        stdout.write(divider_row.as_bytes()).unwrap();
+        stdout.write_all(divider_row.as_bytes()).unwrap();
 
         // draw letters
         stdout.queue(cursor::MoveTo(0, term_h - 1)).unwrap();
-        stdout.write(grid.letters.as_bytes()).unwrap();
+        stdout.write_all(grid.letters.as_bytes()).unwrap();
         stdout.flush()?;
 
         // render at 30fps
         thread::sleep(Duration::from_millis(33));
     }
 
-    let _ = terminal::disable_raw_mode().unwrap();
+    terminal::disable_raw_mode().unwrap();
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_matches_word_hunt_point_scale() {
+        assert_eq!(score(2), 0);
+        assert_eq!(score(3), 100);
+        assert_eq!(score(4), 400);
+        assert_eq!(score(5), 800);
+        assert_eq!(score(6), 1400);
+        assert_eq!(score(7), 1800);
+        assert_eq!(score(8), 2200);
+        assert_eq!(score(10), 3000);
+    }
+
+    #[test]
+    fn trie_insert_get_and_contains_round_trip() {
+        let mut trie: Trie<u32> = Trie::new();
+        trie.insert("cat", 100);
+        trie.insert("cats", 400);
+
+        assert_eq!(trie.get("cat"), Some(&100));
+        assert_eq!(trie.get("cats"), Some(&400));
+        assert_eq!(trie.get("ca"), None);
+        assert!(trie.contains("cat"));
+        assert!(!trie.contains("dog"));
+    }
+
+    #[test]
+    fn for_each_visits_every_value_with_its_full_prefix() {
+        let mut trie: Trie<u32> = Trie::new();
+        trie.insert("cat", 1);
+        trie.insert("car", 2);
+        trie.insert("cart", 3);
+
+        let mut seen: Vec<(String, u32)> = Vec::new();
+        trie.for_each(|word, value| seen.push((word.to_string(), *value)));
+        seen.sort();
+
+        assert_eq!(
+            seen,
+            vec![
+                ("car".to_string(), 2),
+                ("cart".to_string(), 3),
+                ("cat".to_string(), 1),
+            ]
+        );
+    }
+
+    fn trie_with_words(words: &[&str]) -> Trie<()> {
+        let mut trie = Trie::new();
+        for word in words {
+            trie.insert(word, ());
+        }
+        trie
+    }
+
+    #[test]
+    fn wildcard_tile_resolves_to_every_matching_dictionary_word() {
+        let trie = trie_with_words(&["cat", "cot", "cut"]);
+        let mut grid = Grid::new();
+        for c in "c?tx xxxxxxxxxxx".chars().filter(|c| *c != ' ') {
+            grid.add(c);
+        }
+
+        let mut results = find_words(&grid, &trie);
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let words: Vec<&str> = results.iter().map(|(w, _)| w.as_str()).collect();
+        assert_eq!(words, vec!["cat", "cot", "cut"]);
+
+        let expected_path = vec![(0, 0), (0, 1), (0, 2)];
+        for (_, path) in &results {
+            assert_eq!(path, &expected_path);
+        }
+    }
+
+    #[test]
+    fn non_wildcard_tile_only_follows_its_own_letter() {
+        let trie = trie_with_words(&["cat", "cot", "cut"]);
+        let mut grid = Grid::new();
+        for c in "catx xxxxxxxxxxx".chars().filter(|c| *c != ' ') {
+            grid.add(c);
+        }
+
+        let results = find_words(&grid, &trie);
+        let words: Vec<&str> = results.iter().map(|(w, _)| w.as_str()).collect();
+        assert_eq!(words, vec!["cat"]);
+    }
+}
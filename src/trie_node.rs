@@ -0,0 +1,23 @@
+// `include!`'d directly into both `src/main.rs` and `build.rs` (rather than
+// depended on as a shared library module) so the bincode blob `build.rs`
+// embeds and the type `main.rs` deserializes it into can never drift apart
+// in field layout. Whichever file includes this must already have
+// `FxHashMap<K, V>` and `serde::{Serialize, Deserialize}` in scope.
+
+/// A trie node carrying an optional value at terminal nodes, instead of a
+/// bare end-of-word flag, so callers can attach per-word metadata (score,
+/// definition, rarity, ...).
+#[derive(Serialize, Deserialize)]
+struct TrieNode<V> {
+    value: Option<V>,
+    children: FxHashMap<char, TrieNode<V>>,
+}
+
+impl<V> Default for TrieNode<V> {
+    fn default() -> Self {
+        TrieNode {
+            value: None,
+            children: FxHashMap::default(),
+        }
+    }
+}